@@ -1,3 +1,13 @@
+// `std` is on by default; disable it (`--no-default-features`) to build `masked_crc` and
+// `tf_record` for constrained environments that can't link against `std`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+pub mod masked_crc;
+pub mod tf_record;
+
 /// Adds two integers together and returns the result. Must not overflow.
 ///
 /// # Examples
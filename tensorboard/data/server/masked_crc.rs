@@ -1,6 +1,7 @@
 //! Checksums as used by TFRecords.
 
-use std::fmt::{self, Debug, Display};
+use core::fmt::{self, Debug, Display};
+use crc::Hasher32;
 
 /// A CRC-32-C (Castagnoli) checksum after a masking permutation.
 ///
@@ -28,7 +29,7 @@ const CRC_MASK_DELTA: u32 = 0xa282ead8;
 
 /// Apply a masking operation to an unmasked CRC-32-C.
 fn mask(crc: u32) -> MaskedCrc {
-    MaskedCrc(((crc >> 15) | (crc << 17)).wrapping_add(CRC_MASK_DELTA))
+    MaskedCrc(crc.rotate_right(15).wrapping_add(CRC_MASK_DELTA))
 }
 
 impl MaskedCrc {
@@ -47,6 +48,42 @@ impl MaskedCrc {
     pub fn compute(bytes: &[u8]) -> Self {
         mask(crc::crc32::checksum_castagnoli(bytes))
     }
+
+    /// Create an incremental digest, for computing a `MaskedCrc` over data that arrives in
+    /// chunks rather than as a single contiguous buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustboard_core::masked_crc::MaskedCrc;
+    ///
+    /// let mut digest = MaskedCrc::digest();
+    /// digest.update(b"\x1a\x11CRC test, ");
+    /// digest.update(b"one two");
+    /// assert_eq!(digest.finalize(), MaskedCrc(0x5794d08a));
+    /// ```
+    pub fn digest() -> Crc32cDigest {
+        Crc32cDigest(crc::crc32::Digest::new(crc::crc32::CASTAGNOLI))
+    }
+}
+
+/// An in-progress CRC-32-C computation, for checksumming data that arrives piecemeal (e.g. across
+/// multiple reads of a large record) instead of all at once.
+///
+/// Equivalent to buffering all the input and calling [`MaskedCrc::compute`] once the whole buffer
+/// is available, but lets the caller discard each chunk as soon as it's been fed in.
+pub struct Crc32cDigest(crc::crc32::Digest);
+
+impl Crc32cDigest {
+    /// Feed more bytes into the checksum.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    /// Finish the computation, applying the same masking permutation as [`MaskedCrc::compute`].
+    pub fn finalize(self) -> MaskedCrc {
+        mask(self.0.sum32())
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +102,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_digest() {
+        let data = b"\x1a\x11CRC test, one two";
+        assert_eq!(MaskedCrc::digest().finalize(), MaskedCrc::compute(b""));
+
+        let mut digest = MaskedCrc::digest();
+        digest.update(data);
+        assert_eq!(digest.finalize(), MaskedCrc::compute(data));
+
+        // Splitting the same input across multiple `update` calls, at arbitrary boundaries,
+        // should not change the result.
+        let mut digest = MaskedCrc::digest();
+        digest.update(&data[..5]);
+        digest.update(&data[5..11]);
+        digest.update(&data[11..]);
+        assert_eq!(digest.finalize(), MaskedCrc(0x5794d08a));
+    }
+
+    // `format!` lives in `alloc`, not `core`, and isn't imported by this module without `std`.
+    #[cfg(feature = "std")]
     #[test]
     fn test_debug() {
         let long_crc = MaskedCrc(0xf1234567);
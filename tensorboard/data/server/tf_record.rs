@@ -1,9 +1,53 @@
 //! Resumable parsing for TFRecord streams.
 
 use byteorder::{ByteOrder, LittleEndian};
-use std::io::{self, Read};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::masked_crc::{Crc32cDigest, MaskedCrc};
+
+/// Minimal byte-oriented reader abstraction, used in place of [`std::io::Read`] so that this
+/// module builds without `std`.
+///
+/// Mirrors `std::io::Read::read`: `Ok(0)` means the reader is temporarily or permanently out of
+/// data (EOF), while `Err` means a hard I/O failure. [`read_remaining`] relies on this distinction
+/// to tell "nothing new yet, call back later" apart from "abort, the stream is broken".
+pub trait Read {
+    /// The error type produced by a failed read.
+    type Error;
+
+    /// Read some bytes into `buf`, returning the number of bytes read, or `0` at EOF.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Like `read`, but may scatter into multiple buffers in a single underlying operation where
+    /// the reader supports it (e.g. via `readv(2)`). The default implementation just reads into
+    /// the first non-empty buffer and ignores the rest, so it's always safe to call even for
+    /// readers with no real vectored path; callers don't need to query for support first
+    /// (`std::io::Read::is_read_vectored` is still unstable, so we can't rely on it).
+    #[cfg(feature = "std")]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> Result<usize, Self::Error> {
+        match bufs.iter_mut().find(|b| !b.is_empty()) {
+            Some(buf) => self.read(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Read for R {
+    type Error = io::Error;
 
-use crate::masked_crc::MaskedCrc;
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        std::io::Read::read(self, buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        std::io::Read::read_vectored(self, bufs)
+    }
+}
 
 const LENGTH_CRC_OFFSET: usize = 8;
 const DATA_OFFSET: usize = LENGTH_CRC_OFFSET + 4;
@@ -30,6 +74,21 @@ pub struct TfRecordState {
     /// of the data buffer. Once `header.len() == HEADER_LENGTH`, this will have capacity equal to
     /// the data length plus `FOOTER_LENGTH`; before then, it will have no capacity.
     data_plus_footer: Vec<u8>,
+    /// Absolute offset, in bytes from the start of the stream, of the record currently being read
+    /// (i.e., the offset at which `header` started receiving bytes). Only meaningful once `header`
+    /// is non-empty; it is set to `offset` at the start of each call that begins a new record, and
+    /// after a successful read it still holds the finished record's start.
+    record_start: u64,
+    /// Total number of bytes consumed from the stream so far, across every call to `read_record`
+    /// made with this state. Lets callers checkpoint a resume position, or log the byte offset at
+    /// which a corrupt or truncated record was encountered.
+    offset: u64,
+    /// Incremental checksum over the data bytes (not the footer) of `data_plus_footer`, updated
+    /// as each chunk arrives so that the final checksum is available in O(1) once the last chunk
+    /// is read, rather than requiring a second pass over the whole record.
+    digest: Crc32cDigest,
+    /// Number of data bytes (of `data_plus_footer`, excluding the footer) already fed to `digest`.
+    digested: usize,
 }
 
 impl TfRecordState {
@@ -40,8 +99,19 @@ impl TfRecordState {
         TfRecordState {
             header: Vec::with_capacity(HEADER_LENGTH),
             data_plus_footer: Vec::new(),
+            record_start: 0,
+            offset: 0,
+            digest: MaskedCrc::digest(),
+            digested: 0,
         }
     }
+
+    /// The total number of bytes consumed from the stream so far. If the most recent call to
+    /// `read_record` returned `Truncated`, this is the offset at which a subsequent call should
+    /// find new bytes to resume from (assuming no bytes are skipped).
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
 }
 
 impl Default for TfRecordState {
@@ -57,6 +127,9 @@ pub struct TfRecord {
     /// The payload of the TFRecord.
     pub data: Vec<u8>,
     data_crc: MaskedCrc,
+    /// Checksum of `data`, computed incrementally as it was read rather than in `checksum`, so
+    /// that validating a record that has already been fully read is O(1) rather than O(len(data)).
+    actual_crc: MaskedCrc,
 }
 
 /// A buffer's checksum was computed, but it did not match the expected value.
@@ -70,10 +143,10 @@ pub struct ChecksumError {
 }
 
 impl TfRecord {
-    /// Validates the integrity of the record by computing its CRC-32-C and checking it against the
-    /// expected value.
+    /// Validates the integrity of the record by checking its checksum (computed incrementally
+    /// while the record was read) against the expected value.
     pub fn checksum(&self) -> Result<(), ChecksumError> {
-        let got = MaskedCrc::compute(&self.data);
+        let got = self.actual_crc;
         let want = self.data_crc;
         if got == want {
             Ok(())
@@ -83,30 +156,66 @@ impl TfRecord {
     }
 }
 
-/// Error returned by [`TfRecordState::read_record`].
+/// A [`TfRecord`] together with its absolute byte range in the stream, as returned by
+/// [`TfRecordState::read_record`]. Lets a tailing reader log "corruption at byte N" or persist a
+/// checkpoint (`end_offset`) to resume from on restart, instead of re-reading from the top.
+#[derive(Debug)]
+pub struct TfRecordWithOffsets {
+    /// Offset, in bytes from the start of the stream, at which this record began.
+    pub start_offset: u64,
+    /// Offset, in bytes from the start of the stream, of the first byte past this record (i.e.,
+    /// where the next record begins).
+    pub end_offset: u64,
+    /// The decoded record.
+    pub record: TfRecord,
+}
+
+/// Error returned by [`TfRecordState::read_record`], parameterized by the reader's own error type
+/// `E` (`std::io::Error` for any `R: std::io::Read`, or whatever a `no_std` reader reports).
 #[derive(Debug, thiserror::Error)]
-pub enum ReadRecordError {
+pub enum ReadRecordError<E> {
     /// Length field failed checksum. The file is corrupt, and reading must abort.
-    #[error("length checksum mismatch: got {}, want {}", .0.got, .0.want)]
-    BadLengthCrc(ChecksumError),
+    #[error("at offset {offset}: length checksum mismatch: got {}, want {}", .source.got, .source.want)]
+    BadLengthCrc {
+        /// Offset, in bytes from the start of the stream, of the first byte past the 12-byte
+        /// header (length + length-CRC). This is *not* a resync point: a corrupt length-CRC means
+        /// the declared length can't be trusted, so there's no way to know where the real next
+        /// record (if any) actually starts. A caller that wants to skip this record and recover
+        /// has to scan forward from here for a plausible next header, e.g. by re-attempting
+        /// `read_record` at successive offsets until the checksums line up.
+        offset: u64,
+        /// The checksum mismatch itself.
+        source: ChecksumError,
+    },
     /// No fatal errors so far, but the record is not complete. Call `read_record` again with the
     /// same state buffer once new data may be available.
     ///
     /// This includes the "trivial truncation" case where there are no bytes in a new record, so
     /// repeatedly reading records from a file with zero or more well-formed records will always
     /// finish with a `Truncated` error.
-    #[error("record truncated")]
-    Truncated,
+    #[error("at offset {offset}: record truncated")]
+    Truncated {
+        /// Offset, in bytes from the start of the stream, of the first byte not yet received.
+        /// Resuming a reader at this offset (e.g. via `seek`) will pick up exactly where this
+        /// call left off.
+        offset: u64,
+    },
     /// Record is too large to be represented in memory on this system.
     ///
     /// In principle, it would be possible to recover from this error, but in practice this should
     /// rarely occur since serialized protocol buffers do not exceed 2 GiB in size. Thus, no
     /// recovery codepath has been implemented, so reading must abort.
-    #[error("record too large to fit in memory ({0} bytes)")]
-    TooLarge(u64),
-    /// Underlying I/O error. May be retryable if the underlying error is.
+    #[error("at offset {offset}: record too large to fit in memory ({length} bytes)")]
+    TooLarge {
+        /// Offset, in bytes from the start of the stream, of the first byte past the oversized
+        /// record's header.
+        offset: u64,
+        /// The declared length of the record, in bytes.
+        length: u64,
+    },
+    /// Underlying I/O error from the reader. May be retryable if the underlying error is.
     #[error(transparent)]
-    Io(#[from] io::Error),
+    Io(E),
 }
 
 impl TfRecordState {
@@ -138,7 +247,7 @@ impl TfRecordState {
     /// // First attempt: read what we can, then encounter truncation.
     /// assert!(matches!(
     ///     state.read_record(&mut Cursor::new(buf)),
-    ///     Err(ReadRecordError::Truncated)
+    ///     Err(ReadRecordError::Truncated { .. })
     /// ));
     ///
     /// let mut buf: Vec<u8> = Vec::new();
@@ -146,57 +255,233 @@ impl TfRecordState {
     /// buf.extend(b"\x12\x4b\x36\xab"); // data checksum (0xab364b12)
     ///
     /// // Second read: read the rest of the record.
-    /// let record = state.read_record(&mut Cursor::new(buf)).unwrap();
-    /// assert_eq!(record.data, contents);
-    /// assert_eq!(record.checksum(), Ok(()));
+    /// let result = state.read_record(&mut Cursor::new(buf)).unwrap();
+    /// assert_eq!(result.start_offset, 0);
+    /// assert_eq!(result.end_offset, 40);
+    /// assert_eq!(result.record.data, contents);
+    /// assert_eq!(result.record.checksum(), Ok(()));
     /// ```
-    pub fn read_record<R: Read>(&mut self, reader: &mut R) -> Result<TfRecord, ReadRecordError> {
+    pub fn read_record<R: Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<TfRecordWithOffsets, ReadRecordError<R::Error>> {
+        if self.header.is_empty() {
+            self.record_start = self.offset;
+            self.digest = MaskedCrc::digest();
+            self.digested = 0;
+        }
+
         if self.header.len() < self.header.capacity() {
-            read_remaining(reader, &mut self.header)?;
+            read_remaining(reader, &mut self.header, &mut self.offset)?;
 
             let (length_buf, length_crc_buf) = self.header.split_at(LENGTH_CRC_OFFSET);
             let length_crc = MaskedCrc(LittleEndian::read_u32(length_crc_buf));
             let actual_crc = MaskedCrc::compute(length_buf);
             if length_crc != actual_crc {
-                return Err(ReadRecordError::BadLengthCrc(ChecksumError {
-                    got: actual_crc,
-                    want: length_crc,
-                }));
+                return Err(ReadRecordError::BadLengthCrc {
+                    offset: self.offset,
+                    source: ChecksumError {
+                        got: actual_crc,
+                        want: length_crc,
+                    },
+                });
             }
 
             let length = LittleEndian::read_u64(length_buf);
             let data_plus_footer_length_u64 = length + (FOOTER_LENGTH as u64);
             let data_plus_footer_length = data_plus_footer_length_u64 as usize;
             if data_plus_footer_length as u64 != data_plus_footer_length_u64 {
-                return Err(ReadRecordError::TooLarge(length));
+                return Err(ReadRecordError::TooLarge {
+                    offset: self.offset,
+                    length,
+                });
             }
             self.data_plus_footer.reserve_exact(data_plus_footer_length);
         }
 
         if self.data_plus_footer.len() < self.data_plus_footer.capacity() {
-            read_remaining(reader, &mut self.data_plus_footer)?;
+            let read_result = read_remaining(reader, &mut self.data_plus_footer, &mut self.offset);
+            // Digest whatever new data bytes arrived, even if this call came back truncated or
+            // errored, so that a future call that finishes the record doesn't need to re-scan the
+            // chunks already consumed here.
+            let data_capacity = self.data_plus_footer.capacity() - FOOTER_LENGTH;
+            let digested_end = self.data_plus_footer.len().min(data_capacity);
+            if digested_end > self.digested {
+                self.digest.update(&self.data_plus_footer[self.digested..digested_end]);
+                self.digested = digested_end;
+            }
+            read_result?;
         }
 
         let data_length = self.data_plus_footer.len() - FOOTER_LENGTH;
         let data_crc_buf = self.data_plus_footer.split_off(data_length);
-        let data = std::mem::take(&mut self.data_plus_footer);
+        let data = core::mem::take(&mut self.data_plus_footer);
         let data_crc = MaskedCrc(LittleEndian::read_u32(&data_crc_buf));
+        let actual_crc = core::mem::replace(&mut self.digest, MaskedCrc::digest()).finalize();
+        self.digested = 0;
         self.header.clear(); // reset; caller may use this again
-        Ok(TfRecord { data, data_crc })
+        Ok(TfRecordWithOffsets {
+            start_offset: self.record_start,
+            end_offset: self.offset,
+            record: TfRecord {
+                data,
+                data_crc,
+                actual_crc,
+            },
+        })
+    }
+}
+
+/// Writer for TFRecord-formatted streams, producing output that [`TfRecordState`] can parse back.
+///
+/// From TensorFlow `record_writer.cc` comments:
+///  Format of a single record:
+///   uint64    length
+///   uint32    masked crc of length
+///   byte      data[length]
+///   uint32    masked crc of data
+#[cfg(feature = "std")]
+pub struct TfRecordWriter {
+    /// Scratch buffer for the 12-byte header, reused across calls to `write_record` to avoid
+    /// reallocating.
+    header: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl TfRecordWriter {
+    /// Create a writer with an empty scratch buffer.
+    pub fn new() -> Self {
+        TfRecordWriter {
+            header: Vec::with_capacity(HEADER_LENGTH),
+        }
+    }
+
+    /// Write a single record to `writer`: the little-endian length, the length's checksum, the
+    /// data itself, and the data's checksum.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rustboard_core::tf_record::TfRecordWriter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut buf: Vec<u8> = Vec::new();
+    /// let mut writer = TfRecordWriter::new();
+    /// writer
+    ///     .write_record(&mut buf, b"\x09\x00\x00\x80\x38\x99\xd6\xd7\x41\x1a\x0dbrain.Event:2")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     buf,
+    ///     b"\
+    ///         \x18\x00\x00\x00\x00\x00\x00\x00\
+    ///         \xa3\x7f\x4b\x22\
+    ///         \x09\x00\x00\x80\x38\x99\xd6\xd7\x41\x1a\x0dbrain.Event:2\
+    ///         \x12\x4b\x36\xab\
+    ///     "
+    ///     .to_vec()
+    /// );
+    /// ```
+    pub fn write_record<W: Write>(&mut self, writer: &mut W, data: &[u8]) -> io::Result<()> {
+        self.header.clear();
+        self.header.extend_from_slice(&[0; LENGTH_CRC_OFFSET]);
+        LittleEndian::write_u64(&mut self.header, data.len() as u64);
+        let length_crc = MaskedCrc::compute(&self.header);
+        self.header.extend_from_slice(&[0; FOOTER_LENGTH]);
+        LittleEndian::write_u32(&mut self.header[LENGTH_CRC_OFFSET..], length_crc.0);
+
+        writer.write_all(&self.header)?;
+        writer.write_all(data)?;
+
+        let data_crc = MaskedCrc::compute(data);
+        let mut data_crc_buf = [0; FOOTER_LENGTH];
+        LittleEndian::write_u32(&mut data_crc_buf, data_crc.0);
+        writer.write_all(&data_crc_buf)?;
+
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush<W: Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for TfRecordWriter {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Fill `buf`'s remaining capacity from `reader`, or fail with `Truncated` if the reader is dry.
-fn read_remaining<R: Read>(reader: &mut R, buf: &mut Vec<u8>) -> Result<(), ReadRecordError> {
-    let want = buf.capacity() - buf.len();
-    reader.take(want as u64).read_to_end(buf)?;
+/// `*offset` is advanced by exactly the number of bytes actually consumed, regardless of outcome,
+/// so it always reflects the caller's true position in the stream.
+///
+/// This reads directly into `buf`'s spare capacity (no intermediate copy). With the `std` feature
+/// it goes through [`Read::read_vectored`] rather than [`Read::read`], so readers with a genuine
+/// scatter-read (e.g. `readv(2)`) can use it; readers without one just fall back to
+/// `read_vectored`'s default implementation, which behaves exactly like `read`. Without `std`, it
+/// calls `read` directly.
+///
+/// Note this only ever vectors within a *single* field's buffer (`header` or `data_plus_footer`)
+/// — it never passes both in one `read_vectored` call, since `data_plus_footer`'s capacity is
+/// only known once the header's own length field has been read *and its checksum verified* — a
+/// corrupt, partially-written header must not cause us to speculatively reserve and read into an
+/// attacker- or corruption-controlled amount of memory.
+fn read_remaining<R: Read>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    offset: &mut u64,
+) -> Result<(), ReadRecordError<R::Error>> {
+    let start = buf.len();
+    let want = buf.capacity() - start;
+    if want == 0 {
+        return Ok(());
+    }
+    // Grow to capacity up front so reads go straight into `buf`'s storage; on any exit path below
+    // we truncate back down to the number of bytes actually filled before looking at the result,
+    // so `buf.len()` always reflects real data, never the zero-padding used to reserve space.
+    buf.resize(buf.capacity(), 0);
+
+    let mut filled = 0;
+    let mut io_err = None;
+    while filled < want {
+        let dest = &mut buf[start + filled..];
+        let result = {
+            #[cfg(feature = "std")]
+            {
+                let mut slices = [io::IoSliceMut::new(dest)];
+                reader.read_vectored(&mut slices)
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                reader.read(dest)
+            }
+        };
+        match result {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) => {
+                io_err = Some(e);
+                break;
+            }
+        }
+    }
+    buf.truncate(start + filled);
+    *offset += filled as u64;
+
+    if let Some(e) = io_err {
+        return Err(ReadRecordError::Io(e));
+    }
     if buf.len() < buf.capacity() {
-        return Err(ReadRecordError::Truncated);
+        return Err(ReadRecordError::Truncated { offset: *offset });
     }
     Ok(())
 }
 
-#[cfg(test)]
+// The whole suite exercises `TfRecordWriter` and `std::io`-based readers, neither of which exist
+// without `std`.
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use std::collections::VecDeque;
@@ -213,12 +498,14 @@ mod tests {
     }
 
     impl Read for ScriptedReader {
+        type Error = io::Error;
+
         fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
             let sub = match self.0.front_mut() {
                 None => return Ok(0),
                 Some(sub) => sub,
             };
-            let read = sub.read(buf)?;
+            let read = Read::read(sub, buf)?;
             if read == 0 {
                 self.0.pop_front();
             }
@@ -227,6 +514,8 @@ mod tests {
     }
 
     mod scripted_reader_tests {
+        use super::Read;
+
         #[test]
         fn test() {
             let mut sr = super::ScriptedReader::new(vec![
@@ -254,7 +543,6 @@ mod tests {
                 vec![],
             ];
             for expected_data in expected {
-                use std::io::Read;
                 let mut buf = vec![0u8; 3];
                 let n = sr.read(&mut buf).unwrap();
                 assert_eq!(n, expected_data.len());
@@ -321,19 +609,24 @@ mod tests {
             Truncated,
             Record(record_2.to_vec()),
         ];
+        let mut prev_end_offset = 0;
         for (i, step) in steps.into_iter().enumerate() {
             let result = st.read_record(&mut sr);
             match (step, result) {
-                (Truncated, Err(ReadRecordError::Truncated)) => (),
-                (Record(v), Ok(r)) if v == r.data => {
-                    r.checksum()
+                (Truncated, Err(ReadRecordError::Truncated { .. })) => (),
+                (Record(v), Ok(r)) if v == r.record.data => {
+                    r.record
+                        .checksum()
                         .unwrap_or_else(|e| panic!("step {}: checksum failure: {:?}", i + 1, e));
+                    assert_eq!(r.start_offset, prev_end_offset, "step {}", i + 1);
+                    prev_end_offset = r.end_offset;
                 }
                 (step, result) => {
                     panic!("step {}: got {:?}, want {:?}", i + 1, result, step);
                 }
             }
         }
+        assert_eq!(st.offset(), prev_end_offset);
     }
 
     #[test]
@@ -346,10 +639,14 @@ mod tests {
 
         let mut st = TfRecordState::new();
         match st.read_record(&mut Cursor::new(file)) {
-            Err(ReadRecordError::BadLengthCrc(ChecksumError {
-                got: MaskedCrc(0x224b7fa3),
-                want: MaskedCrc(0x554b7f99),
-            })) => (),
+            Err(ReadRecordError::BadLengthCrc {
+                offset: 12,
+                source:
+                    ChecksumError {
+                        got: MaskedCrc(0x224b7fa3),
+                        want: MaskedCrc(0x554b7f99),
+                    },
+            }) => (),
             other => panic!("{:?}", other),
         }
     }
@@ -363,9 +660,9 @@ mod tests {
         file.extend(b"\xdf\x9b\x57\x13"); // 0x13579bdf
 
         let mut st = TfRecordState::new();
-        let record = st.read_record(&mut Cursor::new(file)).expect("read_record");
-        assert_eq!(record.data, b"123456789abcdef012345678".to_vec());
-        match record.checksum() {
+        let result = st.read_record(&mut Cursor::new(file)).expect("read_record");
+        assert_eq!(result.record.data, b"123456789abcdef012345678".to_vec());
+        match result.record.checksum() {
             Err(ChecksumError {
                 want: MaskedCrc(0x13579bdf),
                 got: _,
@@ -376,22 +673,28 @@ mod tests {
 
     #[test]
     fn test_error_display() {
-        let e = ReadRecordError::BadLengthCrc(ChecksumError {
-            got: MaskedCrc(0x01234567),
-            want: MaskedCrc(0xfedcba98),
-        });
+        let e: ReadRecordError<io::Error> = ReadRecordError::BadLengthCrc {
+            offset: 12,
+            source: ChecksumError {
+                got: MaskedCrc(0x01234567),
+                want: MaskedCrc(0xfedcba98),
+            },
+        };
         assert_eq!(
             e.to_string(),
-            "length checksum mismatch: got 0x01234567, want 0xfedcba98"
+            "at offset 12: length checksum mismatch: got 0x01234567, want 0xfedcba98"
         );
 
-        let e = ReadRecordError::Truncated;
-        assert_eq!(e.to_string(), "record truncated");
+        let e: ReadRecordError<io::Error> = ReadRecordError::Truncated { offset: 5 };
+        assert_eq!(e.to_string(), "at offset 5: record truncated");
 
-        let e = ReadRecordError::TooLarge(999);
+        let e: ReadRecordError<io::Error> = ReadRecordError::TooLarge {
+            offset: 12,
+            length: 999,
+        };
         assert_eq!(
             e.to_string(),
-            "record too large to fit in memory (999 bytes)"
+            "at offset 12: record too large to fit in memory (999 bytes)"
         );
 
         let io_error = io::Error::new(io::ErrorKind::BrokenPipe, "pipe machine broke");
@@ -399,4 +702,41 @@ mod tests {
         let e = ReadRecordError::Io(io_error);
         assert_eq!(e.to_string(), expected_message);
     }
+
+    #[test]
+    fn test_write_record() {
+        let mut buf: Vec<u8> = Vec::new();
+        let mut writer = TfRecordWriter::new();
+        writer.write_record(&mut buf, b"123456789abcdef012345678").unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend(b"\x18\x00\x00\x00\x00\x00\x00\x00");
+        expected.extend(b"\xa3\x7f\x4b\x22");
+        expected.extend(b"123456789abcdef012345678");
+        let data_crc = MaskedCrc::compute(b"123456789abcdef012345678");
+        let mut data_crc_buf = [0; FOOTER_LENGTH];
+        LittleEndian::write_u32(&mut data_crc_buf, data_crc.0);
+        expected.extend(&data_crc_buf);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_write_read_round_trip() {
+        let records: Vec<&[u8]> = vec![b"", b"x", b"123456789abcdef012345678"];
+
+        let mut buf: Vec<u8> = Vec::new();
+        let mut writer = TfRecordWriter::new();
+        for record in &records {
+            writer.write_record(&mut buf, record).unwrap();
+        }
+        writer.flush(&mut buf).unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        let mut state = TfRecordState::new();
+        for record in &records {
+            let got = state.read_record(&mut cursor).unwrap();
+            assert_eq!(&got.record.data, record);
+            assert_eq!(got.record.checksum(), Ok(()));
+        }
+    }
 }